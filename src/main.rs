@@ -1,10 +1,19 @@
-use iced::{Command, Element, Settings, Theme, executor, widget::{column, row, text, TextInput, Button, Container, Scrollable, PickList}, Length};
+use iced::{Command, Element, Settings, Theme, Color, executor, widget::{column, row, text, Column, Row, TextInput, Button, Container, Scrollable, PickList}, Length};
 use iced::Application;
+use chrono::{Datelike, Duration, Local, NaiveDate, Weekday};
+use time::OffsetDateTime;
+use rusqlite::Connection;
 use std::fs::{File, OpenOptions};
 use std::io::{BufReader, BufWriter, Write};
+use std::collections::BTreeMap;
 use std::fmt;
 
 const TODO_CSV_PATH: &str = "todos.csv";
+const TODO_TXT_PATH: &str = "todos.txt";
+const TODO_DB_PATH: &str = "todos.db";
+const IMPORT_JSON_PATH: &str = "import.json";
+const IMPORT_TXT_PATH: &str = "import.txt";
+const EXPORT_JSON_PATH: &str = "export.json";
 
 fn main() -> iced::Result {
     ProductivityApp::run(Settings::default())
@@ -12,9 +21,210 @@ fn main() -> iced::Result {
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 struct TodoItem {
+    #[serde(default = "new_id")]
+    id: String,
     description: String,
     completed: bool,
     category: String,
+    priority: Option<char>,
+    projects: Vec<String>,
+    contexts: Vec<String>,
+    tags: BTreeMap<String, String>,
+    #[serde(default)]
+    due: Option<NaiveDate>,
+    #[serde(default)]
+    timers: Vec<TimePoint>,
+}
+
+/// A single tracked interval. `end` is `None` while the timer is running.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct TimePoint {
+    #[serde(with = "time::serde::rfc3339")]
+    start: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option", default)]
+    end: Option<OffsetDateTime>,
+}
+
+impl TimePoint {
+    /// Elapsed time for this interval, measuring an open interval against `now`.
+    fn duration(&self, now: OffsetDateTime) -> time::Duration {
+        self.end.unwrap_or(now) - self.start
+    }
+}
+
+/// Render a [`time::Duration`] as `H:MM:SS`.
+fn format_duration(d: time::Duration) -> String {
+    let total = d.whole_seconds().max(0);
+    format!("{}:{:02}:{:02}", total / 3600, (total % 3600) / 60, total % 60)
+}
+
+/// Generate a fresh stable identifier for a todo.
+fn new_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// Resolve a date input against `today`, accepting ISO `YYYY-MM-DD`, the words
+/// `today`/`tomorrow`, a weekday abbreviation (`mon`..`sun`, the next such day),
+/// or a relative `+Nd` offset.
+fn parse_date(input: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let s = input.trim().to_lowercase();
+    if s.is_empty() {
+        return None;
+    }
+    match s.as_str() {
+        "today" => return Some(today),
+        "tomorrow" => return Some(today + Duration::days(1)),
+        _ => {}
+    }
+    if let Some(rest) = s.strip_prefix('+') {
+        if let Some(days) = rest.strip_suffix('d') {
+            if let Ok(n) = days.parse::<i64>() {
+                return Some(today + Duration::days(n));
+            }
+        }
+    }
+    let weekday = match s.as_str() {
+        "mon" => Some(Weekday::Mon),
+        "tue" => Some(Weekday::Tue),
+        "wed" => Some(Weekday::Wed),
+        "thu" => Some(Weekday::Thu),
+        "fri" => Some(Weekday::Fri),
+        "sat" => Some(Weekday::Sat),
+        "sun" => Some(Weekday::Sun),
+        _ => None,
+    };
+    if let Some(wd) = weekday {
+        let mut d = today + Duration::days(1);
+        while d.weekday() != wd {
+            d += Duration::days(1);
+        }
+        return Some(d);
+    }
+    NaiveDate::parse_from_str(&s, "%Y-%m-%d").ok()
+}
+
+impl TodoItem {
+    /// Build a `TodoItem` from a raw todo.txt line, extracting completion,
+    /// priority, `+project`/`@context` tokens and `key:value` tags while
+    /// keeping `description` reconstructible via [`to_todo_txt`](Self::to_todo_txt).
+    fn parse(raw: &str, category: &str) -> TodoItem {
+        let mut rest = raw.trim();
+        let mut completed = false;
+        if let Some(stripped) = rest.strip_prefix("x ") {
+            completed = true;
+            rest = stripped.trim_start();
+        }
+        let mut priority = None;
+        if rest.len() >= 3 && rest.as_bytes()[0] == b'(' && rest.as_bytes()[2] == b')' {
+            let c = rest.as_bytes()[1] as char;
+            if c.is_ascii_uppercase() && rest[3..].starts_with(' ') {
+                priority = Some(c);
+                rest = rest[3..].trim_start();
+            }
+        }
+        let mut projects = Vec::new();
+        let mut contexts = Vec::new();
+        let mut tags = BTreeMap::new();
+        for token in rest.split_whitespace() {
+            if let Some(p) = token.strip_prefix('+') {
+                if !p.is_empty() && !projects.iter().any(|x| x == p) {
+                    projects.push(p.to_string());
+                }
+            } else if let Some(c) = token.strip_prefix('@') {
+                if !c.is_empty() && !contexts.iter().any(|x| x == c) {
+                    contexts.push(c.to_string());
+                }
+            } else if let Some((k, v)) = token.split_once(':') {
+                if !k.is_empty() && !v.is_empty() {
+                    tags.insert(k.to_string(), v.to_string());
+                }
+            }
+        }
+        let due = tags
+            .get("due")
+            .and_then(|v| NaiveDate::parse_from_str(v, "%Y-%m-%d").ok());
+        TodoItem {
+            id: new_id(),
+            description: rest.to_string(),
+            completed,
+            category: category.trim().to_string(),
+            priority,
+            projects,
+            contexts,
+            tags,
+            due,
+            timers: Vec::new(),
+        }
+    }
+
+    /// Total time tracked across all intervals, counting any running interval.
+    fn tracked_duration(&self, now: OffsetDateTime) -> time::Duration {
+        self.timers
+            .iter()
+            .fold(time::Duration::ZERO, |acc, tp| acc + tp.duration(now))
+    }
+
+    /// Whether a timer is currently running for this item.
+    fn is_timing(&self) -> bool {
+        self.timers.last().map_or(false, |tp| tp.end.is_none())
+    }
+
+    /// Reconstruct the canonical todo.txt line for this item so the list stays
+    /// interchangeable with other todo.txt tools.
+    fn to_todo_txt(&self) -> String {
+        let mut out = String::new();
+        if self.completed {
+            out.push_str("x ");
+        }
+        if let Some(p) = self.priority {
+            out.push('(');
+            out.push(p);
+            out.push_str(") ");
+        }
+        out.push_str(&self.description);
+        // Re-emit the structured fields as todo.txt tokens for any item whose
+        // raw `description` doesn't already carry them (e.g. Taskwarrior
+        // imports), so they survive a round-trip. `category`, `id` and the
+        // tracked intervals are not written to the line.
+        for project in &self.projects {
+            let token = format!("+{}", project);
+            if !self.description.split_whitespace().any(|w| w == token) {
+                out.push(' ');
+                out.push_str(&token);
+            }
+        }
+        for context in &self.contexts {
+            let token = format!("@{}", context);
+            if !self.description.split_whitespace().any(|w| w == token) {
+                out.push(' ');
+                out.push_str(&token);
+            }
+        }
+        if let Some(due) = self.due {
+            let token = format!("due:{}", due);
+            if !self.description.split_whitespace().any(|w| w == token) {
+                out.push(' ');
+                out.push_str(&token);
+            }
+        }
+        for (k, v) in &self.tags {
+            // `due` is already surfaced via the dedicated field above.
+            if k == "due" {
+                continue;
+            }
+            let token = format!("{}:{}", k, v);
+            if !self.description.split_whitespace().any(|w| w == token) {
+                out.push(' ');
+                out.push_str(&token);
+            }
+        }
+        out
+    }
+
+    /// An item is overdue when it has a past due date and is not yet done.
+    fn is_overdue(&self, today: NaiveDate) -> bool {
+        matches!(self.due, Some(due) if due < today) && !self.completed
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -23,9 +233,17 @@ enum Message {
     CategoryInputChanged(String),
     AddTodo,
     ToggleTodoCompleted(usize),
-    ToggleShowCompleted,
+    StatusChanged(TodoStatus),
+    QueryChanged(String),
     SortByCategory,
     FilterCategoryChanged(FilterCategory),
+    DueDateChanged(String),
+    FilterByDate(Option<NaiveDate>),
+    StartTimer(usize),
+    StopTimer(usize),
+    ToggleTimesheet,
+    Import,
+    Export,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -40,18 +258,234 @@ impl fmt::Display for FilterCategory {
     }
 }
 
+/// Which lifecycle subset of todos the list should show. `Empty` selects tasks
+/// whose description is blank; `All` includes them, while `Active` and `Done`
+/// skip empty tasks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TodoStatus {
+    Active,
+    Done,
+    All,
+    Empty,
+}
+
+impl TodoStatus {
+    const ALL: [TodoStatus; 4] = [
+        TodoStatus::Active,
+        TodoStatus::Done,
+        TodoStatus::All,
+        TodoStatus::Empty,
+    ];
+
+    /// Whether `todo` belongs to this status subset.
+    fn matches(&self, todo: &TodoItem) -> bool {
+        let empty = todo.description.trim().is_empty();
+        match self {
+            TodoStatus::Active => !todo.completed && !empty,
+            TodoStatus::Done => todo.completed && !empty,
+            TodoStatus::All => true,
+            TodoStatus::Empty => empty,
+        }
+    }
+}
+
+impl fmt::Display for TodoStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            TodoStatus::Active => "Active",
+            TodoStatus::Done => "Done",
+            TodoStatus::All => "All",
+            TodoStatus::Empty => "Empty",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Comparison operator for a `due:` predicate.
+#[derive(Debug, Clone, Copy)]
+enum DueOp {
+    Eq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// A compiled query predicate over a [`TodoItem`]. Built from a small boolean
+/// grammar (`and`/`or`/`not`, parentheses) over atomic predicates so the filter
+/// pick-list and the text query box feed the same layer.
+#[derive(Debug, Clone)]
+enum Query {
+    /// Always-true leaf, used as the neutral query.
+    Any,
+    Text(String),
+    Project(String),
+    Context(String),
+    Category(String),
+    Priority(char),
+    Due(DueOp, NaiveDate),
+    Not(Box<Query>),
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+}
+
+impl Query {
+    /// Evaluate the predicate against a todo. Relative dates in `due:` atoms are
+    /// already resolved when the query is parsed.
+    fn matches(&self, todo: &TodoItem, today: NaiveDate) -> bool {
+        match self {
+            Query::Any => true,
+            Query::Text(t) => todo.description.to_lowercase().contains(&t.to_lowercase()),
+            Query::Project(p) => todo.projects.iter().any(|x| x == p),
+            Query::Context(c) => todo.contexts.iter().any(|x| x == c),
+            Query::Category(c) => &todo.category == c,
+            Query::Priority(p) => todo.priority == Some(*p),
+            Query::Due(op, date) => match todo.due {
+                Some(due) => match op {
+                    DueOp::Eq => due == *date,
+                    DueOp::Lt => due < *date,
+                    DueOp::Le => due <= *date,
+                    DueOp::Gt => due > *date,
+                    DueOp::Ge => due >= *date,
+                },
+                None => false,
+            },
+            Query::Not(q) => !q.matches(todo, today),
+            Query::And(a, b) => a.matches(todo, today) && b.matches(todo, today),
+            Query::Or(a, b) => a.matches(todo, today) || b.matches(todo, today),
+        }
+    }
+
+    /// Conjoin two queries, collapsing the neutral `Any` so a trivially-empty
+    /// source doesn't add noise to the tree.
+    fn and(self, other: Query) -> Query {
+        match (self, other) {
+            (Query::Any, q) | (q, Query::Any) => q,
+            (a, b) => Query::And(Box::new(a), Box::new(b)),
+        }
+    }
+
+    /// Parse a query string. An unparseable query yields [`Query::Any`] so the
+    /// list stays visible while the user is still typing.
+    fn parse(input: &str, today: NaiveDate) -> Query {
+        let spaced = input.replace('(', " ( ").replace(')', " ) ");
+        let tokens: Vec<&str> = spaced.split_whitespace().collect();
+        let mut pos = 0;
+        parse_or(&tokens, &mut pos, today).unwrap_or(Query::Any)
+    }
+}
+
+/// `or_expr := and_expr ("or" and_expr)*`
+fn parse_or(tokens: &[&str], pos: &mut usize, today: NaiveDate) -> Option<Query> {
+    let mut left = parse_and(tokens, pos, today)?;
+    while tokens.get(*pos).map(|t| t.eq_ignore_ascii_case("or")) == Some(true) {
+        *pos += 1;
+        let right = parse_and(tokens, pos, today)?;
+        left = Query::Or(Box::new(left), Box::new(right));
+    }
+    Some(left)
+}
+
+/// `and_expr := factor (("and")? factor)*` — adjacency implies AND.
+fn parse_and(tokens: &[&str], pos: &mut usize, today: NaiveDate) -> Option<Query> {
+    let mut left = parse_factor(tokens, pos, today)?;
+    loop {
+        match tokens.get(*pos) {
+            None => break,
+            Some(t) if t.eq_ignore_ascii_case("or") || *t == ")" => break,
+            Some(t) if t.eq_ignore_ascii_case("and") => {
+                *pos += 1;
+                let right = parse_factor(tokens, pos, today)?;
+                left = Query::And(Box::new(left), Box::new(right));
+            }
+            Some(_) => {
+                let right = parse_factor(tokens, pos, today)?;
+                left = Query::And(Box::new(left), Box::new(right));
+            }
+        }
+    }
+    Some(left)
+}
+
+/// `factor := "not" factor | "(" or_expr ")" | atom`
+fn parse_factor(tokens: &[&str], pos: &mut usize, today: NaiveDate) -> Option<Query> {
+    match tokens.get(*pos) {
+        Some(t) if t.eq_ignore_ascii_case("not") => {
+            *pos += 1;
+            Some(Query::Not(Box::new(parse_factor(tokens, pos, today)?)))
+        }
+        Some(t) if *t == "(" => {
+            *pos += 1;
+            let inner = parse_or(tokens, pos, today)?;
+            if tokens.get(*pos) == Some(&")") {
+                *pos += 1;
+            }
+            Some(inner)
+        }
+        Some(t) => {
+            *pos += 1;
+            Some(parse_atom(t, today))
+        }
+        None => None,
+    }
+}
+
+/// Parse a single atomic predicate token.
+fn parse_atom(token: &str, today: NaiveDate) -> Query {
+    if let Some(p) = token.strip_prefix('+') {
+        return Query::Project(p.to_string());
+    }
+    if let Some(c) = token.strip_prefix('@') {
+        return Query::Context(c.to_string());
+    }
+    if let Some(p) = token.strip_prefix("pri:") {
+        if let Some(c) = p.chars().next() {
+            return Query::Priority(c.to_ascii_uppercase());
+        }
+    }
+    if let Some(rest) = token.strip_prefix("due:") {
+        let (op, date_str) = if let Some(r) = rest.strip_prefix("<=") {
+            (DueOp::Le, r)
+        } else if let Some(r) = rest.strip_prefix(">=") {
+            (DueOp::Ge, r)
+        } else if let Some(r) = rest.strip_prefix('<') {
+            (DueOp::Lt, r)
+        } else if let Some(r) = rest.strip_prefix('>') {
+            (DueOp::Gt, r)
+        } else {
+            (DueOp::Eq, rest)
+        };
+        if let Some(date) = parse_date(date_str, today) {
+            return Query::Due(op, date);
+        }
+    }
+    Query::Text(token.to_string())
+}
+
 struct ProductivityApp {
+    db: Db,
     todo_input: String,
     category_input: String,
     todos: Vec<TodoItem>,
-    show_completed: bool,
+    status: TodoStatus,
+    query_input: String,
     sort_by_category: bool,
     filter_category: Option<String>,
+    due_input: String,
+    filter_date: Option<NaiveDate>,
+    show_timesheet: bool,
 }
 
 impl ProductivityApp {
+    /// Distinct categories for the new-todo input pick-list. Category-only: the
+    /// `+project`/`@context` tokens belong on the filter pick-list, not here.
     fn categories(&self) -> Vec<String> {
-        let mut cats: Vec<String> = self.todos.iter().map(|t| t.category.clone()).collect();
+        let mut cats: Vec<String> = Vec::new();
+        for todo in &self.todos {
+            if !todo.category.is_empty() {
+                cats.push(todo.category.clone());
+            }
+        }
         if !self.category_input.trim().is_empty() && !cats.contains(&self.category_input) {
             cats.push(self.category_input.clone());
         }
@@ -59,11 +493,111 @@ impl ProductivityApp {
         cats.dedup();
         cats
     }
+    /// Options for the filter pick-list: every category plus the `+project` and
+    /// `@context` tokens so the list can be filtered by any of them.
     fn filter_categories(&self) -> Vec<FilterCategory> {
-        let mut cats: Vec<FilterCategory> = self.categories().into_iter().map(|c| FilterCategory(Some(c))).collect();
+        let mut cats: Vec<String> = self.categories();
+        for todo in &self.todos {
+            cats.extend(todo.projects.iter().map(|p| format!("+{}", p)));
+            cats.extend(todo.contexts.iter().map(|c| format!("@{}", c)));
+        }
+        cats.sort();
+        cats.dedup();
+        let mut cats: Vec<FilterCategory> = cats.into_iter().map(|c| FilterCategory(Some(c))).collect();
         cats.insert(0, FilterCategory(None)); // None means show all
         cats
     }
+
+    /// Build a month-grid calendar for the month containing `today`. Days with
+    /// todos due are highlighted and clicking a day filters the list to it.
+    fn calendar(&self, today: NaiveDate) -> Element<'_, Message> {
+        let first = NaiveDate::from_ymd_opt(today.year(), today.month(), 1).unwrap();
+        // Monday-based offset of the first day within its week.
+        let lead = first.weekday().num_days_from_monday();
+        let header = row![text(first.format("%B %Y").to_string())];
+        let weekdays = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"].iter().fold(
+            Row::new().spacing(4),
+            |r, d| r.push(text(*d).width(Length::Fixed(32.0))),
+        );
+        let mut grid = Column::new().spacing(4).push(header).push(weekdays);
+        let mut week = Row::new().spacing(4);
+        for _ in 0..lead {
+            week = week.push(text("").width(Length::Fixed(32.0)));
+        }
+        let mut slot = lead;
+        let mut day = first;
+        while day.month() == first.month() {
+            let has_due = self.todos.iter().any(|t| t.due == Some(day) && !t.completed);
+            let label = text(day.day().to_string());
+            let label = if has_due { label.style(Color::from_rgb(0.1, 0.4, 0.85)) } else { label };
+            let mut button = Button::new(label)
+                .width(Length::Fixed(32.0))
+                .on_press(Message::FilterByDate(Some(day)));
+            if self.filter_date == Some(day) || day == today {
+                button = button.style(iced::theme::Button::Primary);
+            }
+            week = week.push(button);
+            slot += 1;
+            if slot % 7 == 0 {
+                grid = grid.push(week);
+                week = Row::new().spacing(4);
+            }
+            day += Duration::days(1);
+        }
+        if slot % 7 != 0 {
+            grid = grid.push(week);
+        }
+        grid.into()
+    }
+
+    /// Render all tracked intervals grouped by calendar day, with per-day and
+    /// per-category totals computed by summing `end - start` over intervals.
+    fn timesheet(&self, now: OffsetDateTime) -> Element<'_, Message> {
+        // day -> (day total, category -> total)
+        let mut by_day: BTreeMap<time::Date, (time::Duration, BTreeMap<String, time::Duration>)> =
+            BTreeMap::new();
+        for todo in &self.todos {
+            let cat = if todo.category.is_empty() {
+                "(uncategorized)".to_string()
+            } else {
+                todo.category.clone()
+            };
+            for tp in &todo.timers {
+                let dur = tp.duration(now);
+                let entry = by_day.entry(tp.start.date()).or_insert_with(|| {
+                    (time::Duration::ZERO, BTreeMap::new())
+                });
+                entry.0 += dur;
+                *entry.1.entry(cat.clone()).or_insert(time::Duration::ZERO) += dur;
+            }
+        }
+        let mut list = Column::new()
+            .spacing(8)
+            .push(row![
+                text("Timesheet").size(24),
+                Button::new(text("Back")).on_press(Message::ToggleTimesheet),
+            ].spacing(20));
+        if by_day.is_empty() {
+            list = list.push(text("No tracked time yet."));
+        }
+        for (day, (total, cats)) in by_day {
+            list = list.push(text(format!("{} — {}", day, format_duration(total))).size(18));
+            for (cat, dur) in cats {
+                list = list.push(text(format!("    {}: {}", cat, format_duration(dur))));
+            }
+        }
+        Container::new(Scrollable::new(list)).padding(20).into()
+    }
+}
+
+/// A row of the pre-SQLite `todos.csv`, whose headerless layout was
+/// `description,completed,category`. Mapped explicitly into a `TodoItem` during
+/// the one-time migration so the baseline columns line up.
+#[derive(Debug, serde::Deserialize)]
+struct LegacyCsvRow {
+    description: String,
+    completed: bool,
+    category: String,
 }
 
 impl Application for ProductivityApp {
@@ -73,23 +607,44 @@ impl Application for ProductivityApp {
     type Flags = ();
 
     fn new(_flags: Self::Flags) -> (Self, Command<Self::Message>) {
-        let mut todos = Vec::new();
-        if let Ok(file) = File::open(TODO_CSV_PATH) {
-            let mut rdr = csv::ReaderBuilder::new().has_headers(false).from_reader(BufReader::new(file));
-            for result in rdr.deserialize() {
-                if let Ok(todo) = result {
-                    todos.push(todo);
+        // A locked file or unwritable directory shouldn't crash the app on
+        // startup; fall back to a transient in-memory store so the session
+        // still runs (writes just won't persist past it).
+        let db = Db::open(TODO_DB_PATH).unwrap_or_else(|e| {
+            eprintln!("warning: couldn't open todo store at {TODO_DB_PATH} ({e}); using a transient in-memory store");
+            Db::open_in_memory()
+        });
+        let mut todos = db.load_all();
+        // One-time migration: if the DB is empty but a legacy CSV exists, fold
+        // it into the authoritative store.
+        if todos.is_empty() {
+            if let Ok(file) = File::open(TODO_CSV_PATH) {
+                let mut rdr = csv::ReaderBuilder::new().has_headers(false).from_reader(BufReader::new(file));
+                for result in rdr.deserialize::<LegacyCsvRow>() {
+                    if let Ok(row) = result {
+                        // Run the baseline description through the structured
+                        // parser so any todo.txt tokens it carried are honoured.
+                        let mut todo = TodoItem::parse(&row.description, &row.category);
+                        todo.completed = row.completed;
+                        db.upsert(&todo);
+                        todos.push(todo);
+                    }
                 }
             }
         }
         (
             ProductivityApp {
+                db,
                 todo_input: String::new(),
                 category_input: String::new(),
                 todos,
-                show_completed: false,
+                status: TodoStatus::Active,
+                query_input: String::new(),
                 sort_by_category: false,
                 filter_category: None,
+                due_input: String::new(),
+                filter_date: None,
+                show_timesheet: false,
             },
             Command::none(),
         )
@@ -109,24 +664,28 @@ impl Application for ProductivityApp {
             }
             Message::AddTodo => {
                 if !self.todo_input.trim().is_empty() {
-                    self.todos.push(TodoItem {
-                        description: self.todo_input.trim().to_string(),
-                        completed: false,
-                        category: self.category_input.trim().to_string(),
-                    });
+                    let mut todo = TodoItem::parse(&self.todo_input, &self.category_input);
+                    if let Some(due) = parse_date(&self.due_input, Local::now().date_naive()) {
+                        todo.due = Some(due);
+                    }
+                    self.db.upsert(&todo);
+                    self.todos.push(todo);
                     self.todo_input.clear();
                     self.category_input.clear();
-                    save_todos(&self.todos);
+                    self.due_input.clear();
                 }
             }
             Message::ToggleTodoCompleted(idx) => {
                 if let Some(todo) = self.todos.get_mut(idx) {
                     todo.completed = !todo.completed;
-                    save_todos(&self.todos);
+                    self.db.upsert(todo);
                 }
             }
-            Message::ToggleShowCompleted => {
-                self.show_completed = !self.show_completed;
+            Message::StatusChanged(status) => {
+                self.status = status;
+            }
+            Message::QueryChanged(input) => {
+                self.query_input = input;
             }
             Message::SortByCategory => {
                 self.sort_by_category = !self.sort_by_category;
@@ -137,31 +696,108 @@ impl Application for ProductivityApp {
             Message::FilterCategoryChanged(cat) => {
                 self.filter_category = cat.0;
             }
+            Message::DueDateChanged(input) => {
+                self.due_input = input;
+            }
+            Message::FilterByDate(date) => {
+                // Clicking the already-selected day clears the date filter.
+                self.filter_date = if self.filter_date == date { None } else { date };
+            }
+            Message::StartTimer(idx) => {
+                if let Some(todo) = self.todos.get_mut(idx) {
+                    if !todo.is_timing() {
+                        todo.timers.push(TimePoint {
+                            start: OffsetDateTime::now_utc(),
+                            end: None,
+                        });
+                        self.db.upsert(todo);
+                    }
+                }
+            }
+            Message::StopTimer(idx) => {
+                if let Some(todo) = self.todos.get_mut(idx) {
+                    if todo.timers.last().map_or(false, |tp| tp.end.is_none()) {
+                        if let Some(open) = todo.timers.last_mut() {
+                            open.end = Some(OffsetDateTime::now_utc());
+                        }
+                        self.db.upsert(todo);
+                    }
+                }
+            }
+            Message::ToggleTimesheet => {
+                self.show_timesheet = !self.show_timesheet;
+            }
+            Message::Import => {
+                let incoming = load_imports();
+                for todo in incoming {
+                    merge_todo(&mut self.todos, todo, &self.db);
+                }
+            }
+            Message::Export => {
+                export_csv(&self.todos);
+                export_todo_txt(&self.todos);
+                export_taskwarrior(&self.todos);
+            }
         }
         Command::none()
     }
 
     fn view(&self) -> Element<'_, Self::Message> {
-        let todos_iter = self.todos.iter().enumerate()
-            .filter(|(_, todo)| self.show_completed || !todo.completed)
-            .filter(|(_, todo)| {
-                if let Some(ref cat) = self.filter_category {
-                    &todo.category == cat
-                } else {
-                    true
-                }
-            });
-        let todo_list = todos_iter.fold(
+        let today = Local::now().date_naive();
+        let now = OffsetDateTime::now_utc();
+        if self.show_timesheet {
+            return self.timesheet(now);
+        }
+        // The pick-list and text box are just two sources feeding one predicate.
+        let mut query = Query::parse(&self.query_input, today);
+        if let Some(ref cat) = self.filter_category {
+            let source = if let Some(project) = cat.strip_prefix('+') {
+                Query::Project(project.to_string())
+            } else if let Some(context) = cat.strip_prefix('@') {
+                Query::Context(context.to_string())
+            } else {
+                Query::Category(cat.clone())
+            };
+            query = query.and(source);
+        }
+        if let Some(date) = self.filter_date {
+            query = query.and(Query::Due(DueOp::Eq, date));
+        }
+        let mut visible: Vec<(usize, &TodoItem)> = self.todos.iter().enumerate()
+            .filter(|(_, todo)| self.status.matches(todo))
+            .filter(|(_, todo)| query.matches(todo, today))
+            .collect();
+        // Surface overdue items first so they don't get buried in the list.
+        visible.sort_by_key(|(_, todo)| !todo.is_overdue(today));
+        let todo_list = visible.into_iter().fold(
             column![text("To-Do List:")],
             |col, (idx, todo)| {
                 let check = if todo.completed { "[x]" } else { "[ ]" };
-                col.push(
-                    row![
-                        Button::new(text(check)).on_press(Message::ToggleTodoCompleted(idx)),
-                        text(&todo.description),
-                        text(format!("[{}]", todo.category)),
-                    ]
-                )
+                let overdue = todo.is_overdue(today);
+                let mut desc = text(&todo.description);
+                if overdue {
+                    desc = desc.style(Color::from_rgb(0.85, 0.1, 0.1));
+                }
+                let mut r = row![
+                    Button::new(text(check)).on_press(Message::ToggleTodoCompleted(idx)),
+                    desc,
+                    text(format!("[{}]", todo.category)),
+                ];
+                if let Some(due) = todo.due {
+                    let mut due_label = text(format!("due {}", due));
+                    if overdue {
+                        due_label = due_label.style(Color::from_rgb(0.85, 0.1, 0.1));
+                    }
+                    r = r.push(due_label);
+                }
+                let timer_button = if todo.is_timing() {
+                    Button::new(text("Stop")).on_press(Message::StopTimer(idx))
+                } else {
+                    Button::new(text("Start")).on_press(Message::StartTimer(idx))
+                };
+                r = r.push(timer_button)
+                    .push(text(format_duration(todo.tracked_duration(now))));
+                col.push(r)
             },
         );
         let todo_input = TextInput::new("Add a to-do...", &self.todo_input)
@@ -178,19 +814,28 @@ impl Application for ProductivityApp {
             self.filter_category.clone().map(|c| FilterCategory(Some(c))).or(Some(FilterCategory(None))),
             Message::FilterCategoryChanged,
         ).placeholder("Filter by Category");
+        let due_input = TextInput::new("Due (today, +3d, mon, 2024-06-01)", &self.due_input)
+            .on_input(Message::DueDateChanged)
+            .on_submit(Message::AddTodo);
         let add_button = Button::new(text("Add")).on_press(Message::AddTodo);
-        let show_completed_button = Button::new(
-            text(if self.show_completed { "Hide Completed" } else { "Show Completed" })
-        ).on_press(Message::ToggleShowCompleted);
+        let status_picklist = PickList::new(
+            TodoStatus::ALL.to_vec(),
+            Some(self.status),
+            Message::StatusChanged,
+        );
+        let query_box = TextInput::new("Query (e.g. @home and (pri:A or due:<=today))", &self.query_input)
+            .on_input(Message::QueryChanged);
         let sort_by_category_button = Button::new(
             text(if self.sort_by_category { "Unsort" } else { "Sort by Category" })
         ).on_press(Message::SortByCategory);
-        // Placeholder for calendar widget, since iced does not provide a Calendar widget by default.
-        let calendar_placeholder = text("Calendar widget not implemented");
+        let timesheet_button = Button::new(text("Timesheet")).on_press(Message::ToggleTimesheet);
+        let import_button = Button::new(text("Import")).on_press(Message::Import);
+        let export_button = Button::new(text("Export")).on_press(Message::Export);
+        let calendar = self.calendar(today);
 
         row![
-            column![todo_list, row![todo_input, picklist, add_button, show_completed_button, sort_by_category_button, filter_picklist]].width(Length::FillPortion(1)),
-            column![text("Calendar:"), calendar_placeholder].width(Length::FillPortion(1)),
+            column![todo_list, row![todo_input, due_input, picklist, add_button, status_picklist, sort_by_category_button, timesheet_button, import_button, export_button, filter_picklist], query_box].width(Length::FillPortion(1)),
+            column![text("Calendar:"), calendar].width(Length::FillPortion(1)),
         ]
         .spacing(20)
         .padding(20)
@@ -198,12 +843,337 @@ impl Application for ProductivityApp {
     }
 }
 
-fn save_todos(todos: &Vec<TodoItem>) {
+/// Authoritative embedded store for todos. Each todo is persisted as a JSON
+/// blob keyed by its stable id, so incremental upserts can't corrupt the rest
+/// of the list. Filtering runs in memory over the loaded list via the query
+/// engine, so no per-field columns/indexes are kept here.
+struct Db {
+    conn: Connection,
+}
+
+impl Db {
+    /// Open the store and run the schema migration step.
+    fn open(path: &str) -> rusqlite::Result<Db> {
+        Db::with_connection(Connection::open(path)?)
+    }
+
+    /// Open a transient in-memory store, used as a fallback when the on-disk
+    /// store can't be opened so the app still starts.
+    fn open_in_memory() -> Db {
+        Db::with_connection(Connection::open_in_memory().expect("in-memory sqlite is always available"))
+            .expect("schema migration on a fresh in-memory db cannot fail")
+    }
+
+    /// Run the schema migration step on an open connection.
+    fn with_connection(conn: Connection) -> rusqlite::Result<Db> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS todos (
+                id   TEXT PRIMARY KEY,
+                data TEXT NOT NULL
+            );",
+        )?;
+        Ok(Db { conn })
+    }
+
+    /// Load every todo, ordered for a stable display.
+    fn load_all(&self) -> Vec<TodoItem> {
+        let mut todos = Vec::new();
+        if let Ok(mut stmt) = self.conn.prepare("SELECT data FROM todos ORDER BY rowid") {
+            if let Ok(rows) = stmt.query_map([], |row| row.get::<_, String>(0)) {
+                for blob in rows.flatten() {
+                    if let Ok(todo) = serde_json::from_str::<TodoItem>(&blob) {
+                        todos.push(todo);
+                    }
+                }
+            }
+        }
+        todos
+    }
+
+    /// Insert or update a single todo. Keeps writes incremental so a crash
+    /// mid-mutation cannot corrupt the rest of the list.
+    fn upsert(&self, todo: &TodoItem) {
+        let data = serde_json::to_string(todo).unwrap_or_default();
+        let _ = self.conn.execute(
+            "INSERT INTO todos (id, data)
+             VALUES (?1, ?2)
+             ON CONFLICT(id) DO UPDATE SET data=excluded.data",
+            rusqlite::params![todo.id, data],
+        );
+    }
+}
+
+/// A flat scalar projection of a [`TodoItem`] for CSV export. The `csv` crate
+/// cannot serialize the map and nested-interval fields, so the portable file
+/// carries only the core columns other tools can read.
+#[derive(Debug, serde::Serialize)]
+struct TodoCsvRow<'a> {
+    id: &'a str,
+    description: &'a str,
+    completed: bool,
+    category: &'a str,
+    priority: Option<char>,
+    due: Option<String>,
+}
+
+/// Export the current todos to a portable CSV file. The DB is authoritative;
+/// this only keeps a file interchangeable with other tools.
+fn export_csv(todos: &[TodoItem]) {
     if let Ok(file) = OpenOptions::new().write(true).create(true).truncate(true).open(TODO_CSV_PATH) {
         let mut wtr = csv::WriterBuilder::new().has_headers(false).from_writer(BufWriter::new(file));
         for todo in todos {
-            let _ = wtr.serialize(todo);
+            let row = TodoCsvRow {
+                id: &todo.id,
+                description: &todo.description,
+                completed: todo.completed,
+                category: &todo.category,
+                priority: todo.priority,
+                due: todo.due.map(|d| d.to_string()),
+            };
+            let _ = wtr.serialize(row);
         }
         let _ = wtr.flush();
     }
 }
+
+/// Export the current todos as a standard todo.txt file.
+fn export_todo_txt(todos: &[TodoItem]) {
+    if let Ok(file) = OpenOptions::new().write(true).create(true).truncate(true).open(TODO_TXT_PATH) {
+        let mut w = BufWriter::new(file);
+        for todo in todos {
+            let _ = writeln!(w, "{}", todo.to_todo_txt());
+        }
+        let _ = w.flush();
+    }
+}
+
+/// A Taskwarrior task as emitted by `task export`. Every field except
+/// `description` is optional so we can ingest partial exports.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct TaskwarriorTask {
+    #[serde(default)]
+    uuid: Option<String>,
+    description: String,
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    project: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    due: Option<String>,
+}
+
+/// Parse a Taskwarrior `due` timestamp (`YYYYMMDDTHHMMSSZ`) down to a date.
+fn parse_taskwarrior_date(s: &str) -> Option<NaiveDate> {
+    if s.len() >= 8 {
+        NaiveDate::parse_from_str(&s[..8], "%Y%m%d").ok()
+    } else {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()
+    }
+}
+
+impl From<TaskwarriorTask> for TodoItem {
+    fn from(t: TaskwarriorTask) -> TodoItem {
+        let mut todo = TodoItem::parse(&t.description, t.project.as_deref().unwrap_or(""));
+        todo.id = t.uuid.unwrap_or_else(new_id);
+        todo.completed = t.status.as_deref() == Some("completed");
+        for tag in t.tags {
+            if !todo.contexts.iter().any(|c| c == &tag) {
+                todo.contexts.push(tag);
+            }
+        }
+        if let Some(due) = t.due.as_deref().and_then(parse_taskwarrior_date) {
+            todo.due = Some(due);
+        }
+        todo
+    }
+}
+
+/// Ingest external task sources from the conventional import files: a
+/// Taskwarrior JSON export (`import.json`) and plain todo.txt lines
+/// (`import.txt`).
+fn load_imports() -> Vec<TodoItem> {
+    let mut incoming = Vec::new();
+    if let Ok(data) = std::fs::read_to_string(IMPORT_JSON_PATH) {
+        if let Ok(tasks) = serde_json::from_str::<Vec<TaskwarriorTask>>(&data) {
+            incoming.extend(tasks.into_iter().map(TodoItem::from));
+        }
+    }
+    if let Ok(data) = std::fs::read_to_string(IMPORT_TXT_PATH) {
+        for line in data.lines() {
+            if !line.trim().is_empty() {
+                incoming.push(TodoItem::parse(line, ""));
+            }
+        }
+    }
+    incoming
+}
+
+/// Merge an imported todo into the list, upserting the DB. De-duplicates on the
+/// stable id, falling back to matching description/due so repeated todo.txt
+/// imports (which carry no uuid, and whose export can't round-trip `category`)
+/// merge rather than pile up.
+fn merge_todo(todos: &mut Vec<TodoItem>, incoming: TodoItem, db: &Db) {
+    let existing = todos.iter_mut().find(|t| {
+        t.id == incoming.id
+            || (t.description == incoming.description && t.due == incoming.due)
+    });
+    match existing {
+        Some(slot) => {
+            // Merge rather than replace: keep the existing identity and any
+            // tracked time, overwrite only the fields the source actually
+            // provides, and union the structured collections so nothing the
+            // import doesn't carry is silently dropped.
+            slot.description = incoming.description;
+            slot.completed = incoming.completed;
+            if !incoming.category.is_empty() {
+                slot.category = incoming.category;
+            }
+            if incoming.priority.is_some() {
+                slot.priority = incoming.priority;
+            }
+            if incoming.due.is_some() {
+                slot.due = incoming.due;
+            }
+            for project in incoming.projects {
+                if !slot.projects.contains(&project) {
+                    slot.projects.push(project);
+                }
+            }
+            for context in incoming.contexts {
+                if !slot.contexts.contains(&context) {
+                    slot.contexts.push(context);
+                }
+            }
+            for (k, v) in incoming.tags {
+                slot.tags.insert(k, v);
+            }
+            db.upsert(slot);
+        }
+        None => {
+            db.upsert(&incoming);
+            todos.push(incoming);
+        }
+    }
+}
+
+/// Export the current todos as a Taskwarrior-compatible JSON array.
+fn export_taskwarrior(todos: &[TodoItem]) {
+    let tasks: Vec<TaskwarriorTask> = todos
+        .iter()
+        .map(|t| TaskwarriorTask {
+            uuid: Some(t.id.clone()),
+            description: t.description.clone(),
+            status: Some(if t.completed { "completed".to_string() } else { "pending".to_string() }),
+            project: if t.category.is_empty() { None } else { Some(t.category.clone()) },
+            tags: t.contexts.clone(),
+            due: t.due.map(|d| d.format("%Y%m%dT000000Z").to_string()),
+        })
+        .collect();
+    if let Ok(json) = serde_json::to_string_pretty(&tasks) {
+        let _ = std::fs::write(EXPORT_JSON_PATH, json);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn parse_date_keywords_and_offsets() {
+        let today = date(2024, 6, 1); // a Saturday
+        assert_eq!(parse_date("today", today), Some(today));
+        assert_eq!(parse_date("tomorrow", today), Some(date(2024, 6, 2)));
+        assert_eq!(parse_date("+3d", today), Some(date(2024, 6, 4)));
+        assert_eq!(parse_date("2024-12-25", today), Some(date(2024, 12, 25)));
+        assert_eq!(parse_date("", today), None);
+        assert_eq!(parse_date("nonsense", today), None);
+    }
+
+    #[test]
+    fn parse_date_weekday_picks_next_occurrence() {
+        let today = date(2024, 6, 1); // Saturday
+        // The next Monday after Saturday June 1 is June 3.
+        assert_eq!(parse_date("mon", today), Some(date(2024, 6, 3)));
+        // The weekday lookup never returns today itself.
+        assert_eq!(parse_date("sat", today), Some(date(2024, 6, 8)));
+    }
+
+    #[test]
+    fn todo_parse_extracts_structured_fields() {
+        let todo = TodoItem::parse("x (A) pay rent +home @errands due:2024-06-01", "bills");
+        assert!(todo.completed);
+        assert_eq!(todo.priority, Some('A'));
+        assert_eq!(todo.category, "bills");
+        assert_eq!(todo.projects, vec!["home".to_string()]);
+        assert_eq!(todo.contexts, vec!["errands".to_string()]);
+        assert_eq!(todo.tags.get("due"), Some(&"2024-06-01".to_string()));
+        assert_eq!(todo.due, Some(date(2024, 6, 1)));
+    }
+
+    #[test]
+    fn todo_parse_plain_line_has_no_tokens() {
+        let todo = TodoItem::parse("buy milk", "");
+        assert!(!todo.completed);
+        assert_eq!(todo.priority, None);
+        assert!(todo.projects.is_empty());
+        assert!(todo.contexts.is_empty());
+        assert_eq!(todo.due, None);
+    }
+
+    fn todo_with(desc: &str, cat: &str) -> TodoItem {
+        TodoItem::parse(desc, cat)
+    }
+
+    #[test]
+    fn query_atoms_match_expected_items() {
+        let today = date(2024, 6, 1);
+        let home = todo_with("mow lawn +garden @home", "chores");
+        let work = todo_with("(A) ship release", "work");
+
+        assert!(Query::parse("@home", today).matches(&home, today));
+        assert!(!Query::parse("@home", today).matches(&work, today));
+        assert!(Query::parse("+garden", today).matches(&home, today));
+        assert!(Query::parse("pri:A", today).matches(&work, today));
+        assert!(Query::parse("lawn", today).matches(&home, today));
+    }
+
+    #[test]
+    fn query_boolean_grammar() {
+        let today = date(2024, 6, 1);
+        let mut home = todo_with("tidy up @home", "chores");
+        home.priority = Some('A');
+
+        // AND by adjacency, OR, NOT, and parentheses.
+        assert!(Query::parse("@home pri:A", today).matches(&home, today));
+        assert!(!Query::parse("@home and pri:B", today).matches(&home, today));
+        assert!(Query::parse("@work or pri:A", today).matches(&home, today));
+        assert!(Query::parse("not @work", today).matches(&home, today));
+        assert!(Query::parse("@home and (pri:A or pri:B)", today).matches(&home, today));
+    }
+
+    #[test]
+    fn query_due_comparisons() {
+        let today = date(2024, 6, 1);
+        let mut item = todo_with("file taxes", "");
+        item.due = Some(date(2024, 6, 1));
+
+        assert!(Query::parse("due:today", today).matches(&item, today));
+        assert!(Query::parse("due:<=today", today).matches(&item, today));
+        assert!(!Query::parse("due:<today", today).matches(&item, today));
+        assert!(Query::parse("due:>=2024-05-01", today).matches(&item, today));
+    }
+
+    #[test]
+    fn query_unparseable_is_any() {
+        let today = date(2024, 6, 1);
+        let item = todo_with("anything", "");
+        // An empty query leaves everything visible.
+        assert!(Query::parse("", today).matches(&item, today));
+    }
+}